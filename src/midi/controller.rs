@@ -1,21 +1,53 @@
+use std::collections::{HashMap, VecDeque};
 use std::convert::TryFrom;
-use std::sync::mpsc::{channel, Receiver};
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::{Duration, Instant};
 use std::usize;
 
-use anyhow::Result;
-
-use midir::{Ignore, MidiInput};
+use anyhow::{Context, Result};
 
 use wvr_data::types::DataHolder;
 use wvr_data::types::InputProvider;
 
+use super::device_manager::DeviceManager;
+use super::midifile::{self, TimedMidiEvent};
+
+const DEFAULT_RECORDING_BPM: f32 = 120.0;
+
+struct Recording {
+    path: PathBuf,
+    start_time: f64,
+    events: Vec<(f64, Vec<u8>)>,
+}
+
+const CLOCK_RING_SIZE: usize = 24;
+const TICKS_PER_QUARTER_NOTE: f64 = 24.0;
+
+// Number of consecutive outlier ticks that must agree with each other before
+// a tempo change is treated as real and the clock ring is resynced. Without
+// this, a tempo change of more than 2x keeps every subsequent tick rejected
+// forever since they're always compared to the same stale mean.
+const CLOCK_RESYNC_OUTLIER_STREAK: u8 = 4;
+const CHROMATIC_SCALE: u16 = 0b1111_1111_1111;
+
+// Minimum time between reconnect attempts while a device is unplugged, so
+// polling `get()` once per uniform per frame doesn't hammer the OS MIDI
+// subsystem with a port re-enumeration dozens of times a second.
+const RECONNECT_RETRY_INTERVAL: Duration = Duration::from_millis(1000);
+
 pub struct MidiProvider {
     name: String,
 
     time: f64,
 
-    _port: midir::MidiInputConnection<()>,
-    midi_input_channel: Receiver<Vec<u8>>,
+    device_manager: DeviceManager,
+    port_name: String,
+    connected: bool,
+    last_reconnect_attempt: Option<Instant>,
+
+    _port: Option<midir::MidiInputConnection<()>>,
+    midi_input_channel: Option<Receiver<Vec<u8>>>,
 
     pressed: [bool; 1024],
     pressed_time: [f64; 1024],
@@ -24,64 +56,373 @@ pub struct MidiProvider {
     toggled_time: [f64; 1024],
 
     values: [u8; 1024],
+
+    clock_ticks: VecDeque<Instant>,
+    clock_tick_count: u64,
+    clock_bpm: f32,
+    clock_running: bool,
+    clock_outlier_run: u8,
+    clock_last_outlier_interval: f64,
+
+    recording: Option<Recording>,
+
+    scale_root: u8,
+    scale_mask: u16,
+    scale_voices: u8,
+    quantized: [bool; 1024],
+    quantized_refcount: [u8; 1024],
+    emitted_notes: HashMap<usize, Vec<usize>>,
+
+    midi_output: Option<midir::MidiOutputConnection>,
+    led_channel: u8,
+    led_velocity: u8,
+    led_mirrored: [bool; 1024],
+
+    velocity: [u8; 1024],
+    pitch_bend: f32,
+    aftertouch: u8,
+    poly_aftertouch: [u8; 1024],
+    program: u8,
+
+    channel_filter: Option<u8>,
+    channel_pressed: [[bool; 128]; 16],
+    channel_toggled: [[bool; 128]; 16],
+    channel_values: [[u8; 128]; 16],
 }
 
 impl MidiProvider {
     pub fn new(name: String, port_name: String) -> Result<Self> {
-        let mut midi_in = MidiInput::new(&name).unwrap();
-        midi_in.ignore(Ignore::None);
-
-        for i in 0..midi_in.port_count() {
-            println!("{:?}", midi_in.port_name(i).unwrap());
-
-            if midi_in.port_name(i).unwrap().contains(&port_name) {
-                let (port, midi_input_channel) = {
-                    let (tx, rx) = channel();
-
-                    let port_name = midi_in.port_name(i).unwrap();
-
-                    let port = midi_in
-                        .connect(
-                            i,
-                            &port_name,
-                            move |_timestamp, midi_message, _| {
-                                tx.send(midi_message.to_vec())
-                                    .expect("Could not send midi message to midi message receiver");
-                            },
-                            (),
-                        )
-                        .unwrap();
-
-                    (port, rx)
+        let device_manager = DeviceManager::new(name.clone());
+        let (port, midi_input_channel) = device_manager.connect(&port_name)?;
+        // Best-effort: not every input device also exposes an output (LED
+        // feedback) port, so a failure here is not fatal.
+        let midi_output = device_manager.connect_output(&port_name).ok();
+
+        Ok(MidiProvider {
+            name,
+            time: 0.0,
+
+            device_manager,
+            port_name,
+            connected: true,
+            last_reconnect_attempt: None,
+
+            _port: Some(port),
+            midi_input_channel: Some(midi_input_channel),
+
+            pressed: [false; 1024],
+            pressed_time: [0.0; 1024],
+            toggled: [false; 1024],
+            toggled_time: [0.0; 1024],
+
+            values: [0; 1024],
+
+            clock_ticks: VecDeque::with_capacity(CLOCK_RING_SIZE),
+            clock_tick_count: 0,
+            clock_bpm: 0.0,
+            clock_running: false,
+            clock_outlier_run: 0,
+            clock_last_outlier_interval: 0.0,
+
+            recording: None,
+
+            scale_root: 0,
+            scale_mask: CHROMATIC_SCALE,
+            scale_voices: 1,
+            quantized: [false; 1024],
+            quantized_refcount: [0; 1024],
+            emitted_notes: HashMap::new(),
+
+            midi_output,
+            led_channel: 0,
+            led_velocity: 127,
+            led_mirrored: [false; 1024],
+
+            velocity: [0; 1024],
+            pitch_bend: 0.0,
+            aftertouch: 0,
+            poly_aftertouch: [0; 1024],
+            program: 0,
+
+            channel_filter: None,
+            channel_pressed: [[false; 128]; 16],
+            channel_toggled: [[false; 128]; 16],
+            channel_values: [[0; 128]; 16],
+        })
+    }
+
+    fn reconnect(&mut self) {
+        if let Some(last_attempt) = self.last_reconnect_attempt {
+            if last_attempt.elapsed() < RECONNECT_RETRY_INTERVAL {
+                return;
+            }
+        }
+        self.last_reconnect_attempt = Some(Instant::now());
+
+        if let Ok((port, midi_input_channel)) = self.device_manager.connect(&self.port_name) {
+            self._port = Some(port);
+            self.midi_input_channel = Some(midi_input_channel);
+            self.connected = true;
+        } else {
+            self.connected = false;
+        }
+    }
+
+    fn reconnect_output(&mut self) {
+        if let Ok(output) = self.device_manager.connect_output(&self.port_name) {
+            self.midi_output = Some(output);
+        }
+    }
+
+    fn send_led(&mut self, pad: u8, velocity: u8) {
+        if self.midi_output.is_none() {
+            self.reconnect_output();
+        }
+
+        let status = 0x90 | (self.led_channel & 0x0F);
+        let send_failed = match self.midi_output.as_mut() {
+            Some(output) => output.send(&[status, pad, velocity]).is_err(),
+            None => false,
+        };
+
+        if send_failed {
+            self.midi_output = None;
+        }
+    }
+
+    fn mirror_toggled_leds(&mut self) {
+        for pad in 0..128u8 {
+            let index = pad as usize;
+            if self.toggled[index] != self.led_mirrored[index] {
+                let velocity = if self.toggled[index] {
+                    self.led_velocity
+                } else {
+                    0
                 };
+                self.send_led(pad, velocity);
+                self.led_mirrored[index] = self.toggled[index];
+            }
+        }
+    }
+
+    fn channel_matches(&self, channel: u8) -> bool {
+        self.channel_filter.map_or(true, |filter| filter == channel)
+    }
+
+    fn set_channel_pressed(&mut self, channel: u8, note_number: u8, pressed: bool) {
+        if channel as usize >= self.channel_pressed.len() {
+            return;
+        }
 
-                return Ok(MidiProvider {
-                    name,
-                    time: 0.0,
+        let channel = channel as usize;
+        let note_number = note_number as usize;
+        let was_pressed = self.channel_pressed[channel][note_number];
 
-                    _port: port,
-                    midi_input_channel,
+        self.channel_pressed[channel][note_number] = pressed;
 
-                    pressed: [false; 1024],
-                    pressed_time: [0.0; 1024],
-                    toggled: [false; 1024],
-                    toggled_time: [0.0; 1024],
+        if was_pressed != pressed {
+            self.channel_toggled[channel][note_number] =
+                !self.channel_toggled[channel][note_number];
+        }
+    }
+
+    fn reset_clock(&mut self, running: bool) {
+        self.clock_ticks.clear();
+        self.clock_tick_count = 0;
+        self.clock_running = running;
+        self.clock_outlier_run = 0;
+    }
 
-                    values: [0; 1024],
-                });
+    fn on_clock_tick(&mut self) {
+        let now = Instant::now();
+
+        if let Some(&last) = self.clock_ticks.back() {
+            let interval = now.duration_since(last).as_secs_f64();
+
+            if self.clock_ticks.len() >= 2 {
+                let mean_interval = mean_interval(&self.clock_ticks);
+                if interval < mean_interval * 0.5 || interval > mean_interval * 2.0 {
+                    // Outlier tick (e.g. scheduling jitter): drop it instead
+                    // of letting it skew the BPM estimate, unless enough
+                    // consecutive outliers agree with each other -- that's
+                    // a genuine tempo change, not jitter, so resync onto it
+                    // instead of rejecting ticks against the stale mean
+                    // forever.
+                    let agrees_with_last_outlier = self.clock_outlier_run > 0
+                        && (interval - self.clock_last_outlier_interval).abs()
+                            < self.clock_last_outlier_interval * 0.25;
+
+                    self.clock_outlier_run = if agrees_with_last_outlier {
+                        self.clock_outlier_run + 1
+                    } else {
+                        1
+                    };
+                    self.clock_last_outlier_interval = interval;
+
+                    if self.clock_outlier_run < CLOCK_RESYNC_OUTLIER_STREAK {
+                        return;
+                    }
+
+                    self.clock_ticks.clear();
+                }
+            }
+        }
+
+        self.clock_outlier_run = 0;
+
+        if self.clock_ticks.len() == CLOCK_RING_SIZE {
+            self.clock_ticks.pop_front();
+        }
+        self.clock_ticks.push_back(now);
+        self.clock_tick_count += 1;
+
+        if self.clock_ticks.len() >= 2 {
+            let mean_interval = mean_interval(&self.clock_ticks);
+            if mean_interval > 0.0 {
+                self.clock_bpm = (60.0 / (mean_interval * TICKS_PER_QUARTER_NOTE)) as f32;
+            }
+        }
+    }
+
+    pub fn start_recording(&mut self, path: PathBuf) {
+        self.recording = Some(Recording {
+            path,
+            start_time: self.time,
+            events: Vec::new(),
+        });
+    }
+
+    pub fn stop_recording(&mut self) -> Result<()> {
+        let recording = self
+            .recording
+            .take()
+            .context("MidiProvider is not currently recording")?;
+
+        let bpm = if self.clock_running && self.clock_bpm > 0.0 {
+            self.clock_bpm
+        } else {
+            DEFAULT_RECORDING_BPM
+        };
+
+        let mut timed_events = Vec::with_capacity(recording.events.len());
+        let mut last_tick = 0;
+        for (time, bytes) in recording.events {
+            let tick = midifile::seconds_to_ticks(time - recording.start_time, bpm);
+            timed_events.push(TimedMidiEvent {
+                delta_ticks: tick.saturating_sub(last_tick),
+                bytes,
+            });
+            last_tick = tick;
+        }
+
+        midifile::write_smf(&recording.path, &timed_events)
+    }
+
+    fn quantize_note(&self, note: u8) -> u8 {
+        let degree_of = |n: i32| (n - self.scale_root as i32).rem_euclid(12);
+
+        if self.scale_mask & (1 << degree_of(note as i32)) != 0 {
+            return note;
+        }
+
+        for offset in 1..=6 {
+            for candidate in [note as i32 - offset, note as i32 + offset] {
+                if (0..=127).contains(&candidate)
+                    && self.scale_mask & (1 << degree_of(candidate)) != 0
+                {
+                    return candidate as u8;
+                }
             }
         }
 
-        Result::Err(std::io::Error::new(
-            std::io::ErrorKind::NotFound,
-            format!(
-                r#"Could not find midi device with matching port name matching "{:}""#,
-                port_name
-            ),
-        ))?
+        note
+    }
+
+    fn scale_chord(&self, root_note: u8, voices: u8) -> Vec<u8> {
+        let mut notes = vec![root_note];
+
+        if self.scale_mask == 0 {
+            return notes;
+        }
+
+        let mut current = root_note as i32;
+        for _ in 1..voices {
+            let mut active_steps_found = 0;
+            while active_steps_found < 2 {
+                current += 1;
+                if current > 127 {
+                    return notes;
+                }
+                let degree = (current - self.scale_root as i32).rem_euclid(12);
+                if self.scale_mask & (1 << degree) != 0 {
+                    active_steps_found += 1;
+                }
+            }
+            notes.push(current as u8);
+        }
+
+        notes
+    }
+
+    fn press_quantized(&mut self, source_note: u8) {
+        let source_note = source_note as usize;
+        let was_pressed = self.pressed[source_note];
+
+        self.pressed[source_note] = true;
+        if !was_pressed {
+            self.toggled[source_note] = !self.toggled[source_note];
+            self.pressed_time[source_note] = self.time;
+            self.toggled_time[source_note] = self.time;
+        }
+
+        let quantized_root = self.quantize_note(source_note as u8);
+        let chord = self.scale_chord(quantized_root, self.scale_voices);
+
+        for &note in &chord {
+            let note = note as usize;
+            self.quantized_refcount[note] = self.quantized_refcount[note].saturating_add(1);
+            self.quantized[note] = true;
+        }
+
+        self.emitted_notes
+            .insert(source_note, chord.into_iter().map(usize::from).collect());
+    }
+
+    // Quantized notes are reference-counted through quantized_refcount: a
+    // chord tone shared between two held source notes stays quantized until
+    // both release.
+    fn release_quantized(&mut self, source_note: u8) {
+        let source_note = source_note as usize;
+        let was_pressed = self.pressed[source_note];
+
+        self.pressed[source_note] = false;
+        if was_pressed {
+            self.toggled[source_note] = !self.toggled[source_note];
+            self.toggled_time[source_note] = self.time;
+        }
+
+        let notes = self
+            .emitted_notes
+            .remove(&source_note)
+            .unwrap_or_default();
+
+        for note in notes {
+            self.quantized_refcount[note] = self.quantized_refcount[note].saturating_sub(1);
+            if self.quantized_refcount[note] == 0 {
+                self.quantized[note] = false;
+            }
+        }
     }
 }
 
+fn mean_interval(ticks: &VecDeque<Instant>) -> f64 {
+    let intervals = ticks.len() - 1;
+    let span = ticks[ticks.len() - 1]
+        .duration_since(ticks[0])
+        .as_secs_f64();
+    span / intervals as f64
+}
+
 impl InputProvider for MidiProvider {
     fn set_name(&mut self, name: &str) {
         self.name = name.to_owned();
@@ -92,66 +433,192 @@ impl InputProvider for MidiProvider {
             format!("{:}.pressed", self.name),
             format!("{:}.toggled", self.name),
             format!("{:}.values", self.name),
+            format!("{:}.bpm", self.name),
+            format!("{:}.beat", self.name),
+            format!("{:}.running", self.name),
+            format!("{:}.connected", self.name),
+            format!("{:}.quantized", self.name),
+            format!("{:}.velocity", self.name),
+            format!("{:}.pitch_bend", self.name),
+            format!("{:}.aftertouch", self.name),
+            format!("{:}.program", self.name),
         ]
     }
 
-    fn set_property(&mut self, _property: &str, _value: &DataHolder) {}
+    fn set_property(&mut self, property: &str, value: &DataHolder) {
+        if let Some(pad) = property.strip_prefix("led.") {
+            if let (Ok(pad), DataHolder::Int(velocity)) = (pad.parse::<u8>(), value) {
+                self.send_led(pad, (*velocity).clamp(0, 127) as u8);
+            }
+            return;
+        }
 
-    fn get(&mut self, uniform_name: &str, _invalidate: bool) -> Option<DataHolder> {
-        while let Ok(message) = self.midi_input_channel.try_recv() {
-            if message.is_empty() {
-                continue;
+        match property {
+            "root" => {
+                if let DataHolder::Int(root) = value {
+                    self.scale_root = (*root).clamp(0, 11) as u8;
+                }
+            }
+            "scale" => {
+                if let DataHolder::Int(mask) = value {
+                    self.scale_mask = (*mask).clamp(0, i32::from(CHROMATIC_SCALE)) as u16;
+                }
+            }
+            "voices" => {
+                if let DataHolder::Int(voices) = value {
+                    self.scale_voices = (*voices).max(1) as u8;
+                }
+            }
+            "led_channel" => {
+                if let DataHolder::Int(channel) = value {
+                    self.led_channel = (*channel as u8) & 0x0F;
+                }
             }
+            "led_velocity" => {
+                if let DataHolder::Int(velocity) = value {
+                    self.led_velocity = (*velocity).clamp(0, 127) as u8;
+                }
+            }
+            "channel" => {
+                if let DataHolder::Int(channel) = value {
+                    self.channel_filter = (0..16).contains(channel).then(|| *channel as u8);
+                }
+            }
+            _ => (),
+        }
+    }
 
-            if let Ok(midi_message) = wmidi::MidiMessage::try_from(message.as_ref()) {
-                match midi_message {
-                    wmidi::MidiMessage::ControlChange(_channel, control_number, control_value) => {
-                        let control_number = u8::from(control_number.0);
+    fn get(&mut self, uniform_name: &str, _invalidate: bool) -> Option<DataHolder> {
+        if self.midi_input_channel.is_none() {
+            // The device was unplugged (or never found): try to reconnect,
+            // debounced by `reconnect()` so polling doesn't hammer the port.
+            self.reconnect();
+        }
 
-                        self.values[control_number as usize] = u8::from(control_value);
+        let midi_input_channel = self.midi_input_channel.take();
+        let mut lost_connection = false;
+
+        if let Some(midi_input_channel) = &midi_input_channel {
+            loop {
+                let message = match midi_input_channel.try_recv() {
+                    Ok(message) => message,
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        lost_connection = true;
+                        break;
+                    }
+                };
 
-                        println!("val {:} ({:})", control_number, self.name);
+                if message.is_empty() {
+                    continue;
+                }
+
+                if let Some(recording) = self.recording.as_mut() {
+                    if midifile::is_recordable(message[0]) {
+                        recording.events.push((self.time, message.clone()));
                     }
-                    wmidi::MidiMessage::NoteOn(_channel, note_number, note_value) => {
-                        let note_value = u8::from(note_value);
-                        let note_number = note_number as usize;
+                }
 
-                        let was_pressed = self.pressed[note_number];
+                if let Ok(midi_message) = wmidi::MidiMessage::try_from(message.as_ref()) {
+                    match midi_message {
+                        wmidi::MidiMessage::ControlChange(
+                            channel,
+                            control_number,
+                            control_value,
+                        ) => {
+                            let channel = u8::from(channel);
+                            let control_number = u8::from(control_number.0);
+                            let control_value = u8::from(control_value);
+
+                            self.channel_values[channel as usize][control_number as usize] =
+                                control_value;
+
+                            if self.channel_matches(channel) {
+                                self.values[control_number as usize] = control_value;
+                            }
 
-                        if note_value > 0 {
-                            self.pressed[note_number] = true;
-                        } else {
-                            self.pressed[note_number] = false
+                            println!("val {:} ({:})", control_number, self.name);
                         }
-
-                        if !was_pressed && self.pressed[note_number] {
-                            self.toggled[note_number] = !self.toggled[note_number];
-                            if self.pressed[note_number] {
-                                self.pressed_time[note_number] = self.time;
+                        wmidi::MidiMessage::NoteOn(channel, note_number, note_value) => {
+                            let channel = u8::from(channel);
+                            let note_number = u8::from(note_number);
+                            let velocity = u8::from(note_value);
+
+                            self.set_channel_pressed(channel, note_number, velocity > 0);
+
+                            if self.channel_matches(channel) {
+                                self.velocity[note_number as usize] = velocity;
+
+                                if velocity > 0 {
+                                    self.press_quantized(note_number);
+                                } else {
+                                    // A NoteOn with velocity 0 is a NoteOff in disguise.
+                                    self.release_quantized(note_number);
+                                }
                             }
-                            self.toggled_time[note_number] = self.time;
+
+                            println!("on {:} ({:})", note_number, self.name);
                         }
+                        wmidi::MidiMessage::NoteOff(channel, note_number, _note_value) => {
+                            let channel = u8::from(channel);
+                            let note_number = u8::from(note_number);
 
-                        println!("on {:} ({:})", note_number, self.name);
-                    }
-                    wmidi::MidiMessage::NoteOff(_channel, note_number, _note_value) => {
-                        let note_number = note_number as usize;
-                        let was_pressed = self.pressed[note_number];
+                            self.set_channel_pressed(channel, note_number, false);
 
-                        self.pressed[note_number] = false;
+                            if self.channel_matches(channel) {
+                                self.release_quantized(note_number);
+                            }
 
-                        if was_pressed != self.pressed[note_number] {
-                            self.toggled[note_number] = !self.toggled[note_number];
-                            self.toggled_time[note_number] = self.time;
+                            println!("of {:} ({:})", note_number, self.name);
                         }
-
-                        println!("of {:} ({:})", note_number, self.name);
+                        wmidi::MidiMessage::PitchBendChange(channel, bend) => {
+                            if self.channel_matches(u8::from(channel)) {
+                                let raw = u16::from(bend) as f32;
+                                self.pitch_bend = (raw - 8192.0) / 8192.0;
+                            }
+                        }
+                        wmidi::MidiMessage::ChannelPressure(channel, pressure) => {
+                            if self.channel_matches(u8::from(channel)) {
+                                self.aftertouch = u8::from(pressure);
+                            }
+                        }
+                        wmidi::MidiMessage::PolyphonicKeyPressure(
+                            channel,
+                            note_number,
+                            pressure,
+                        ) => {
+                            if self.channel_matches(u8::from(channel)) {
+                                self.poly_aftertouch[u8::from(note_number) as usize] =
+                                    u8::from(pressure);
+                            }
+                        }
+                        wmidi::MidiMessage::ProgramChange(channel, program) => {
+                            if self.channel_matches(u8::from(channel)) {
+                                self.program = u8::from(program);
+                            }
+                        }
+                        wmidi::MidiMessage::TimingClock => self.on_clock_tick(),
+                        wmidi::MidiMessage::Start | wmidi::MidiMessage::Continue => {
+                            self.reset_clock(true)
+                        }
+                        wmidi::MidiMessage::Stop => self.reset_clock(false),
+                        message => println!("{:?}", message),
                     }
-                    message => println!("{:?}", message),
                 }
             }
         }
 
+        if lost_connection {
+            self._port = None;
+            self.midi_input_channel = None;
+            self.connected = false;
+            self.reconnect();
+        } else {
+            self.midi_input_channel = midi_input_channel;
+        }
+
+        self.mirror_toggled_leds();
+
         if uniform_name.starts_with("pressed_time") {
             if let Ok(index) = uniform_name.split('.').nth(1)?.parse::<usize>() {
                 if index < self.pressed_time.len() {
@@ -180,6 +647,53 @@ impl InputProvider for MidiProvider {
                 }
             }
         }
+        if uniform_name.starts_with("quantized") {
+            if let Ok(index) = uniform_name.split('.').nth(1)?.parse::<usize>() {
+                if index < self.quantized.len() {
+                    return Some(DataHolder::Bool(self.quantized[index]));
+                }
+            }
+        }
+        if uniform_name.starts_with("velocity") {
+            if let Ok(index) = uniform_name.split('.').nth(1)?.parse::<usize>() {
+                if index < self.velocity.len() {
+                    return Some(DataHolder::Int(self.velocity[index] as i32));
+                }
+            }
+        }
+        if uniform_name.starts_with("poly_aftertouch") {
+            if let Ok(index) = uniform_name.split('.').nth(1)?.parse::<usize>() {
+                if index < self.poly_aftertouch.len() {
+                    return Some(DataHolder::Int(self.poly_aftertouch[index] as i32));
+                }
+            }
+        }
+
+        if let Some(rest) = uniform_name.strip_prefix(&format!("{:}.channel.", self.name)) {
+            let mut parts = rest.splitn(2, '.');
+            if let Some(channel) = parts.next().and_then(|c| c.parse::<usize>().ok()) {
+                if channel < self.channel_pressed.len() {
+                    match parts.next() {
+                        Some("pressed") => {
+                            return Some(DataHolder::BoolArray(
+                                self.channel_pressed[channel].to_vec(),
+                            ))
+                        }
+                        Some("toggled") => {
+                            return Some(DataHolder::BoolArray(
+                                self.channel_toggled[channel].to_vec(),
+                            ))
+                        }
+                        Some("values") => {
+                            return Some(DataHolder::ByteArray(
+                                self.channel_values[channel].to_vec(),
+                            ))
+                        }
+                        _ => (),
+                    }
+                }
+            }
+        }
 
         if uniform_name == format!("{:}.pressed", self.name) {
             Some(DataHolder::BoolArray(self.pressed.to_vec()))
@@ -187,6 +701,25 @@ impl InputProvider for MidiProvider {
             Some(DataHolder::BoolArray(self.toggled.to_vec()))
         } else if uniform_name == format!("{:}.values", self.name) {
             Some(DataHolder::ByteArray(self.values.to_vec()))
+        } else if uniform_name == format!("{:}.bpm", self.name) {
+            Some(DataHolder::Float(self.clock_bpm))
+        } else if uniform_name == format!("{:}.beat", self.name) {
+            let beat = (self.clock_tick_count as f64 / TICKS_PER_QUARTER_NOTE) % 4.0;
+            Some(DataHolder::Float(beat as f32))
+        } else if uniform_name == format!("{:}.running", self.name) {
+            Some(DataHolder::Bool(self.clock_running))
+        } else if uniform_name == format!("{:}.connected", self.name) {
+            Some(DataHolder::Bool(self.connected))
+        } else if uniform_name == format!("{:}.quantized", self.name) {
+            Some(DataHolder::BoolArray(self.quantized.to_vec()))
+        } else if uniform_name == format!("{:}.velocity", self.name) {
+            Some(DataHolder::ByteArray(self.velocity.to_vec()))
+        } else if uniform_name == format!("{:}.pitch_bend", self.name) {
+            Some(DataHolder::Float(self.pitch_bend))
+        } else if uniform_name == format!("{:}.aftertouch", self.name) {
+            Some(DataHolder::Int(self.aftertouch as i32))
+        } else if uniform_name == format!("{:}.program", self.name) {
+            Some(DataHolder::Int(self.program as i32))
         } else {
             None
         }
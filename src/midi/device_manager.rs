@@ -0,0 +1,81 @@
+use std::sync::mpsc::{channel, Receiver};
+
+use anyhow::{anyhow, Result};
+
+use midir::{Ignore, MidiInput, MidiOutput, MidiOutputConnection};
+
+// Recreates the underlying `MidiInput`/`MidiOutput` client on every call
+// since `midir`'s `connect` consumes it, which lets providers re-enumerate
+// and reconnect to a device that was unplugged.
+pub struct DeviceManager {
+    client_name: String,
+}
+
+impl DeviceManager {
+    pub fn new(client_name: String) -> Self {
+        DeviceManager { client_name }
+    }
+
+    pub fn list(&self) -> Result<Vec<String>> {
+        let midi_in = MidiInput::new(&self.client_name)?;
+
+        Ok((0..midi_in.port_count())
+            .filter_map(|i| midi_in.port_name(i).ok())
+            .collect())
+    }
+
+    pub fn connect(
+        &self,
+        port_name: &str,
+    ) -> Result<(midir::MidiInputConnection<()>, Receiver<Vec<u8>>)> {
+        let mut midi_in = MidiInput::new(&self.client_name)?;
+        midi_in.ignore(Ignore::None);
+
+        for i in 0..midi_in.port_count() {
+            let name = midi_in.port_name(i)?;
+
+            if name.contains(port_name) {
+                let (tx, rx) = channel();
+
+                let connection = midi_in
+                    .connect(
+                        i,
+                        &name,
+                        move |_timestamp, midi_message, _| {
+                            let _ = tx.send(midi_message.to_vec());
+                        },
+                        (),
+                    )
+                    .map_err(|err| {
+                        anyhow!(r#"Could not connect to midi port "{:}": {:}"#, name, err)
+                    })?;
+
+                return Ok((connection, rx));
+            }
+        }
+
+        Err(anyhow!(
+            r#"Could not find midi device with port name matching "{:}""#,
+            port_name
+        ))
+    }
+
+    pub fn connect_output(&self, port_name: &str) -> Result<MidiOutputConnection> {
+        let midi_out = MidiOutput::new(&self.client_name)?;
+
+        for i in 0..midi_out.port_count() {
+            let name = midi_out.port_name(i)?;
+
+            if name.contains(port_name) {
+                return midi_out.connect(i, &name).map_err(|err| {
+                    anyhow!(r#"Could not connect to midi output "{:}": {:}"#, name, err)
+                });
+            }
+        }
+
+        Err(anyhow!(
+            r#"Could not find midi output device with port name matching "{:}""#,
+            port_name
+        ))
+    }
+}
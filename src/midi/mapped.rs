@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::str::FromStr;
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+
+use wvr_data::types::DataHolder;
+use wvr_data::types::InputProvider;
+
+use super::device_manager::DeviceManager;
+
+// A tap more than this many seconds after the previous one starts a fresh
+// tap sequence instead of producing a near-zero BPM.
+const TAP_TEMPO_TIMEOUT_SECS: f32 = 2.0;
+
+// Minimum time between reconnect attempts while a device is unplugged, so
+// polling `get()` once per uniform per frame doesn't hammer the OS MIDI
+// subsystem with a port re-enumeration dozens of times a second.
+const RECONNECT_RETRY_INTERVAL: Duration = Duration::from_millis(1000);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MappingKind {
+    ControlChange,
+    Note,
+}
+
+impl FromStr for MappingKind {
+    type Err = anyhow::Error;
+
+    fn from_str(kind: &str) -> Result<Self> {
+        match kind {
+            "cc" => Ok(MappingKind::ControlChange),
+            "note" => Ok(MappingKind::Note),
+            kind => Err(anyhow!(r#"Unknown mapping kind "{:}""#, kind)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MappingMode {
+    Value,
+    Toggle,
+    Momentum,
+    TapTempo,
+}
+
+impl FromStr for MappingMode {
+    type Err = anyhow::Error;
+
+    fn from_str(mode: &str) -> Result<Self> {
+        match mode {
+            "value" => Ok(MappingMode::Value),
+            "toggle" => Ok(MappingMode::Toggle),
+            "momentum" => Ok(MappingMode::Momentum),
+            "taptempo" => Ok(MappingMode::TapTempo),
+            mode => Err(anyhow!(r#"Unknown mapping mode "{:}""#, mode)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Mapping {
+    pub name: String,
+    pub kind: MappingKind,
+    pub channel: u8,
+    pub number: u8,
+    pub mode: MappingMode,
+}
+
+pub struct MappedProvider {
+    device_manager: DeviceManager,
+    port_name: String,
+
+    _port: Option<midir::MidiInputConnection<()>>,
+    midi_input_channel: Option<Receiver<Vec<u8>>>,
+    last_reconnect_attempt: Option<Instant>,
+
+    mappings: Vec<Mapping>,
+
+    pressed: HashMap<String, bool>,
+    toggled: HashMap<String, bool>,
+    values: HashMap<String, f32>,
+    bpm: HashMap<String, f32>,
+    last_tap: HashMap<String, Instant>,
+}
+
+impl MappedProvider {
+    pub fn new(port_name: &str, mappings: Vec<Mapping>) -> Result<Self> {
+        let device_manager = DeviceManager::new("wvr-midi mapped input".to_owned());
+        let (port, midi_input_channel) = device_manager.connect(port_name)?;
+
+        Ok(MappedProvider {
+            device_manager,
+            port_name: port_name.to_owned(),
+
+            _port: Some(port),
+            midi_input_channel: Some(midi_input_channel),
+            last_reconnect_attempt: None,
+
+            mappings,
+
+            pressed: HashMap::new(),
+            toggled: HashMap::new(),
+            values: HashMap::new(),
+            bpm: HashMap::new(),
+            last_tap: HashMap::new(),
+        })
+    }
+
+    fn reconnect(&mut self) {
+        if let Some(last_attempt) = self.last_reconnect_attempt {
+            if last_attempt.elapsed() < RECONNECT_RETRY_INTERVAL {
+                return;
+            }
+        }
+        self.last_reconnect_attempt = Some(Instant::now());
+
+        if let Ok((port, midi_input_channel)) = self.device_manager.connect(&self.port_name) {
+            self._port = Some(port);
+            self.midi_input_channel = Some(midi_input_channel);
+        }
+    }
+
+    fn mapping_for(
+        &self,
+        kind: MappingKind,
+        channel: u8,
+        number: u8,
+    ) -> Option<(String, MappingMode)> {
+        self.mappings
+            .iter()
+            .find(|mapping| {
+                mapping.kind == kind && mapping.channel == channel && mapping.number == number
+            })
+            .map(|mapping| (mapping.name.clone(), mapping.mode))
+    }
+
+    fn apply(&mut self, name: &str, mode: MappingMode, raw_value: u8) {
+        let was_pressed = self.pressed.get(name).copied().unwrap_or(false);
+        let pressed = raw_value > 0;
+        self.pressed.insert(name.to_owned(), pressed);
+
+        match mode {
+            MappingMode::Value => {
+                self.values
+                    .insert(name.to_owned(), raw_value as f32 / 127.0);
+            }
+            MappingMode::Toggle => {
+                if pressed && !was_pressed {
+                    let toggled = self.toggled.entry(name.to_owned()).or_insert(false);
+                    *toggled = !*toggled;
+                }
+            }
+            MappingMode::Momentum => (),
+            MappingMode::TapTempo => {
+                if pressed && !was_pressed {
+                    if let Some(last_tap) = self.last_tap.get(name) {
+                        let elapsed = last_tap.elapsed().as_secs_f32();
+                        if elapsed > 0.0 && elapsed <= TAP_TEMPO_TIMEOUT_SECS {
+                            self.bpm.insert(name.to_owned(), 60.0 / elapsed);
+                        }
+                    }
+                    self.last_tap.insert(name.to_owned(), Instant::now());
+                }
+            }
+        }
+    }
+}
+
+impl InputProvider for MappedProvider {
+    fn set_name(&mut self, _name: &str) {}
+
+    fn provides(&self) -> Vec<String> {
+        self.mappings
+            .iter()
+            .map(|mapping| mapping.name.clone())
+            .collect()
+    }
+
+    fn set_property(&mut self, _property: &str, _value: &DataHolder) {}
+
+    fn get(&mut self, uniform_name: &str, _invalidate: bool) -> Option<DataHolder> {
+        if self.midi_input_channel.is_none() {
+            self.reconnect();
+        }
+
+        let midi_input_channel = self.midi_input_channel.take();
+        let mut lost_connection = false;
+
+        if let Some(midi_input_channel) = &midi_input_channel {
+            loop {
+                let message = match midi_input_channel.try_recv() {
+                    Ok(message) => message,
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        lost_connection = true;
+                        break;
+                    }
+                };
+
+                if message.is_empty() {
+                    continue;
+                }
+
+                if let Ok(midi_message) = wmidi::MidiMessage::try_from(message.as_ref()) {
+                    match midi_message {
+                        wmidi::MidiMessage::ControlChange(
+                            channel,
+                            control_number,
+                            control_value,
+                        ) => {
+                            let channel = u8::from(channel);
+                            let number = u8::from(control_number.0);
+                            let raw_value = u8::from(control_value);
+
+                            if let Some((name, mode)) =
+                                self.mapping_for(MappingKind::ControlChange, channel, number)
+                            {
+                                self.apply(&name, mode, raw_value);
+                            }
+                        }
+                        wmidi::MidiMessage::NoteOn(channel, note_number, note_value) => {
+                            let channel = u8::from(channel);
+                            let number = u8::from(note_number);
+                            let raw_value = u8::from(note_value);
+
+                            if let Some((name, mode)) =
+                                self.mapping_for(MappingKind::Note, channel, number)
+                            {
+                                self.apply(&name, mode, raw_value);
+                            }
+                        }
+                        wmidi::MidiMessage::NoteOff(channel, note_number, _note_value) => {
+                            let channel = u8::from(channel);
+                            let number = u8::from(note_number);
+
+                            if let Some((name, mode)) =
+                                self.mapping_for(MappingKind::Note, channel, number)
+                            {
+                                self.apply(&name, mode, 0);
+                            }
+                        }
+                        _ => (),
+                    }
+                }
+            }
+        }
+
+        if lost_connection {
+            self._port = None;
+            self.midi_input_channel = None;
+            self.reconnect();
+        } else {
+            self.midi_input_channel = midi_input_channel;
+        }
+
+        let mapping = self
+            .mappings
+            .iter()
+            .find(|mapping| mapping.name == uniform_name)?;
+
+        match mapping.mode {
+            MappingMode::Value => Some(DataHolder::Float(
+                self.values.get(uniform_name).copied().unwrap_or(0.0),
+            )),
+            MappingMode::TapTempo => self.bpm.get(uniform_name).copied().map(DataHolder::Float),
+            MappingMode::Toggle => Some(DataHolder::Bool(
+                self.toggled.get(uniform_name).copied().unwrap_or(false),
+            )),
+            MappingMode::Momentum => Some(DataHolder::Bool(
+                self.pressed.get(uniform_name).copied().unwrap_or(false),
+            )),
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use anyhow::{ensure, Result};
+
+use wvr_data::types::DataHolder;
+use wvr_data::types::InputProvider;
+
+pub const TICKS_PER_QUARTER_NOTE: u16 = 480;
+
+#[derive(Debug, Clone)]
+pub struct TimedMidiEvent {
+    pub delta_ticks: u32,
+    pub bytes: Vec<u8>,
+}
+
+pub fn write_smf(path: &Path, events: &[TimedMidiEvent]) -> Result<()> {
+    let mut file = File::create(path)?;
+
+    file.write_all(b"MThd")?;
+    file.write_all(&6u32.to_be_bytes())?; // header length
+    file.write_all(&0u16.to_be_bytes())?; // format 0
+    file.write_all(&1u16.to_be_bytes())?; // one track
+    file.write_all(&TICKS_PER_QUARTER_NOTE.to_be_bytes())?;
+
+    let mut track = Vec::new();
+    for event in events {
+        write_vlq(&mut track, event.delta_ticks);
+        track.extend_from_slice(&event.bytes);
+    }
+    write_vlq(&mut track, 0);
+    track.extend_from_slice(&[0xFF, 0x2F, 0x00]);
+
+    file.write_all(b"MTrk")?;
+    file.write_all(&(track.len() as u32).to_be_bytes())?;
+    file.write_all(&track)?;
+
+    Ok(())
+}
+
+pub fn read_smf(path: &Path) -> Result<Vec<TimedMidiEvent>> {
+    let mut bytes = Vec::new();
+    File::open(path)?.read_to_end(&mut bytes)?;
+
+    ensure!(
+        bytes.len() >= 14 && &bytes[0..4] == b"MThd",
+        "not a Standard MIDI File"
+    );
+
+    let header_len = read_u32(&bytes, 4);
+    let mut cursor = 8 + header_len as usize;
+
+    ensure!(
+        bytes.len() >= cursor + 8 && &bytes[cursor..cursor + 4] == b"MTrk",
+        "missing MTrk chunk"
+    );
+    let track_len = read_u32(&bytes, cursor + 4) as usize;
+    cursor += 8;
+    let track_end = cursor + track_len;
+
+    let mut events = Vec::new();
+    while cursor < track_end {
+        let delta_ticks = read_vlq(&bytes, &mut cursor);
+
+        if bytes[cursor..].starts_with(&[0xFF, 0x2F, 0x00]) {
+            break;
+        }
+
+        let len = midi_message_len(bytes[cursor]);
+        let event_bytes = bytes[cursor..cursor + len].to_vec();
+        cursor += len;
+
+        events.push(TimedMidiEvent {
+            delta_ticks,
+            bytes: event_bytes,
+        });
+    }
+
+    Ok(events)
+}
+
+// System Real-Time messages (0xF8-0xFF) are a single status byte with no
+// data bytes, unlike the channel voice messages below. System Common
+// messages (0xF0-0xF7, including SysEx) are variable-length and aren't
+// handled here; callers must filter them out before recording, via
+// `is_recordable`.
+fn midi_message_len(status: u8) -> usize {
+    if status >= 0xF8 {
+        return 1;
+    }
+
+    match status & 0xF0 {
+        0xC0 | 0xD0 => 2, // Program Change, Channel Pressure
+        _ => 3,           // NoteOn/NoteOff/PolyKeyPressure/ControlChange/PitchBend
+    }
+}
+
+// Whether a raw MIDI message can be written to and replayed from a Standard
+// MIDI File by this module: channel voice messages (0x80-0xEF) and System
+// Real-Time messages (0xF8-0xFF), both of which `midi_message_len` can size.
+// System Common messages (0xF0-0xF7, including SysEx) are variable-length
+// and must be dropped by the recorder instead of being pushed to the file.
+pub fn is_recordable(status: u8) -> bool {
+    status < 0xF0 || status >= 0xF8
+}
+
+fn write_vlq(buffer: &mut Vec<u8>, mut value: u32) {
+    let mut stack = vec![(value & 0x7F) as u8];
+    value >>= 7;
+    while value > 0 {
+        stack.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+    buffer.extend(stack.into_iter().rev());
+}
+
+fn read_vlq(bytes: &[u8], cursor: &mut usize) -> u32 {
+    let mut value = 0u32;
+    loop {
+        let byte = bytes[*cursor];
+        *cursor += 1;
+        value = (value << 7) | (byte & 0x7F) as u32;
+        if byte & 0x80 == 0 {
+            break;
+        }
+    }
+    value
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+pub fn seconds_to_ticks(seconds: f64, bpm: f32) -> u32 {
+    (seconds * bpm as f64 / 60.0 * TICKS_PER_QUARTER_NOTE as f64).max(0.0) as u32
+}
+
+pub fn ticks_to_seconds(ticks: u32, bpm: f32) -> f64 {
+    ticks as f64 / TICKS_PER_QUARTER_NOTE as f64 * 60.0 / bpm as f64
+}
+
+pub struct MidiFileProvider {
+    name: String,
+
+    events: Vec<(f64, Vec<u8>)>,
+    next_event_index: usize,
+
+    time: f64,
+
+    pressed: [bool; 1024],
+    pressed_time: [f64; 1024],
+
+    toggled: [bool; 1024],
+    toggled_time: [f64; 1024],
+
+    values: [u8; 1024],
+}
+
+impl MidiFileProvider {
+    pub fn new(name: String, path: &Path, bpm: f32) -> Result<Self> {
+        let mut elapsed_ticks = 0;
+        let events = read_smf(path)?
+            .into_iter()
+            .map(|event| {
+                elapsed_ticks += event.delta_ticks;
+                (ticks_to_seconds(elapsed_ticks, bpm), event.bytes)
+            })
+            .collect();
+
+        Ok(MidiFileProvider {
+            name,
+
+            events,
+            next_event_index: 0,
+
+            time: 0.0,
+
+            pressed: [false; 1024],
+            pressed_time: [0.0; 1024],
+            toggled: [false; 1024],
+            toggled_time: [0.0; 1024],
+
+            values: [0; 1024],
+        })
+    }
+
+    fn apply_event(&mut self, bytes: &[u8]) {
+        if let Ok(midi_message) = wmidi::MidiMessage::try_from(bytes) {
+            match midi_message {
+                wmidi::MidiMessage::ControlChange(_channel, control_number, control_value) => {
+                    let control_number = u8::from(control_number.0);
+                    self.values[control_number as usize] = u8::from(control_value);
+                }
+                wmidi::MidiMessage::NoteOn(_channel, note_number, note_value) => {
+                    let note_number = note_number as usize;
+                    let was_pressed = self.pressed[note_number];
+
+                    self.pressed[note_number] = u8::from(note_value) > 0;
+
+                    if !was_pressed && self.pressed[note_number] {
+                        self.toggled[note_number] = !self.toggled[note_number];
+                        self.pressed_time[note_number] = self.time;
+                        self.toggled_time[note_number] = self.time;
+                    }
+                }
+                wmidi::MidiMessage::NoteOff(_channel, note_number, _note_value) => {
+                    let note_number = note_number as usize;
+                    let was_pressed = self.pressed[note_number];
+
+                    self.pressed[note_number] = false;
+
+                    if was_pressed != self.pressed[note_number] {
+                        self.toggled[note_number] = !self.toggled[note_number];
+                        self.toggled_time[note_number] = self.time;
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl InputProvider for MidiFileProvider {
+    fn set_name(&mut self, name: &str) {
+        self.name = name.to_owned();
+    }
+
+    fn provides(&self) -> Vec<String> {
+        vec![
+            format!("{:}.pressed", self.name),
+            format!("{:}.toggled", self.name),
+            format!("{:}.values", self.name),
+        ]
+    }
+
+    fn set_property(&mut self, _property: &str, _value: &DataHolder) {}
+
+    fn get(&mut self, uniform_name: &str, _invalidate: bool) -> Option<DataHolder> {
+        if uniform_name.starts_with("pressed_time") {
+            if let Ok(index) = uniform_name.split('.').nth(1)?.parse::<usize>() {
+                if index < self.pressed_time.len() {
+                    return Some(DataHolder::Float(self.pressed_time[index] as f32));
+                }
+            }
+        }
+        if uniform_name.starts_with("pressed") {
+            if let Ok(index) = uniform_name.split('.').nth(1)?.parse::<usize>() {
+                if index < self.pressed.len() {
+                    return Some(DataHolder::Bool(self.pressed[index]));
+                }
+            }
+        }
+        if uniform_name.starts_with("toggled") {
+            if let Ok(index) = uniform_name.split('.').nth(1)?.parse::<usize>() {
+                if index < self.pressed.len() {
+                    return Some(DataHolder::Bool(self.toggled[index]));
+                }
+            }
+        }
+        if uniform_name.starts_with("value") {
+            if let Ok(index) = uniform_name.split('.').nth(1)?.parse::<usize>() {
+                if index < self.pressed.len() {
+                    return Some(DataHolder::Int(self.values[index] as i32));
+                }
+            }
+        }
+
+        if uniform_name == format!("{:}.pressed", self.name) {
+            Some(DataHolder::BoolArray(self.pressed.to_vec()))
+        } else if uniform_name == format!("{:}.toggled", self.name) {
+            Some(DataHolder::BoolArray(self.toggled.to_vec()))
+        } else if uniform_name == format!("{:}.values", self.name) {
+            Some(DataHolder::ByteArray(self.values.to_vec()))
+        } else {
+            None
+        }
+    }
+
+    fn set_time(&mut self, time: f64, _sync: bool) {
+        if time < self.time {
+            // Rewound: replay the file from the start.
+            self.next_event_index = 0;
+            self.pressed = [false; 1024];
+            self.toggled = [false; 1024];
+            self.values = [0; 1024];
+        }
+        self.time = time;
+
+        while let Some((event_time, bytes)) = self.events.get(self.next_event_index) {
+            if *event_time > self.time {
+                break;
+            }
+
+            let bytes = bytes.clone();
+            self.apply_event(&bytes);
+            self.next_event_index += 1;
+        }
+    }
+}